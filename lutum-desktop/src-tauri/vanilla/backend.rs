@@ -0,0 +1,243 @@
+// Backend process lifecycle: spawn, graceful shutdown, crash supervision.
+// =================================================================
+// The spawned `Child` used to be stored and forgotten, so a backend crash
+// mid-session left the app running against a dead port with no recovery.
+// This module spawns the backend, watches it, and restarts it with
+// exponential backoff when it dies unexpectedly.
+
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::python::PythonDiscovery;
+use crate::BACKEND_PORT;
+
+#[cfg(windows)]
+use std::os::windows::process::CommandExt;
+
+// How long the backend gets to exit on its own after being asked to shut
+// down, before we escalate to a hard kill.
+const SHUTDOWN_WAIT_TIMEOUT: Duration = Duration::from_secs(5);
+const SHUTDOWN_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+const EXIT_POLL_INTERVAL: Duration = Duration::from_millis(300);
+const RESTART_BACKOFF_BASE: Duration = Duration::from_secs(1);
+const RESTART_BACKOFF_MAX: Duration = Duration::from_secs(30);
+// A run lasting at least this long is considered healthy and resets the
+// backoff back to `RESTART_BACKOFF_BASE`.
+const HEALTHY_RUN_THRESHOLD: Duration = Duration::from_secs(60);
+
+pub struct BackendProcess(pub Mutex<Option<Child>>);
+
+// Set by `shutdown_and_wait` (on `RunEvent::ExitRequested`) so the supervisor
+// can tell an intentional shutdown apart from a crash and stop restarting
+// the backend.
+pub struct ShuttingDown(pub AtomicBool);
+
+impl Default for ShuttingDown {
+    fn default() -> Self {
+        ShuttingDown(AtomicBool::new(false))
+    }
+}
+
+struct BackendPaths {
+    executable: PathBuf,
+    backend_main: PathBuf,
+    backend_dir: PathBuf,
+    virtual_env: Option<PathBuf>,
+}
+
+fn build_command(paths: &BackendPaths) -> Command {
+    let mut command = Command::new(&paths.executable);
+    command.arg(&paths.backend_main).current_dir(&paths.backend_dir);
+
+    #[cfg(windows)]
+    {
+        let log_file = std::fs::File::create(paths.backend_dir.join("backend_stderr.log"))
+            .map(Stdio::from)
+            .unwrap_or(Stdio::null());
+        // CREATE_NO_WINDOW hides the console; CREATE_NEW_PROCESS_GROUP makes
+        // the backend the head of its own process group, which is required
+        // for GenerateConsoleCtrlEvent(CTRL_BREAK_EVENT, pid) to target it
+        // during graceful shutdown - without it the event has no valid group
+        // to signal and silently fails.
+        command
+            .stdout(Stdio::null())
+            .stderr(log_file)
+            .creation_flags(0x08000000 | 0x00000200); // CREATE_NO_WINDOW | CREATE_NEW_PROCESS_GROUP
+    }
+    #[cfg(not(windows))]
+    {
+        command.stdout(Stdio::null()).stderr(Stdio::null());
+    }
+
+    if let Some(venv_dir) = &paths.virtual_env {
+        command.env("VIRTUAL_ENV", venv_dir);
+    }
+
+    command
+}
+
+fn spawn(paths: &BackendPaths) -> std::io::Result<Child> {
+    build_command(paths).spawn()
+}
+
+fn shutting_down(app: &AppHandle) -> bool {
+    app.state::<ShuttingDown>().0.load(Ordering::SeqCst)
+}
+
+/// Spawn the backend and, if that succeeds, hand it off to a supervisor
+/// thread that restarts it on an unexpected crash.
+pub fn start(app: &AppHandle, backend_main: PathBuf, backend_dir: PathBuf, discovery: PythonDiscovery) {
+    let paths = BackendPaths {
+        executable: discovery.executable,
+        backend_main,
+        backend_dir,
+        virtual_env: discovery.virtual_env,
+    };
+
+    match spawn(&paths) {
+        Ok(child) => {
+            tracing::info!("Backend started with system Python on port {}", BACKEND_PORT);
+            *app.state::<BackendProcess>().0.lock().unwrap() = Some(child);
+            supervise(app.clone(), paths);
+        }
+        Err(e) => {
+            tracing::warn!("Failed to start backend: {}", e);
+        }
+    }
+}
+
+// Waits for the currently-managed child to exit, then restarts it with
+// exponential backoff, unless the app is intentionally shutting down.
+fn supervise(app: AppHandle, paths: BackendPaths) {
+    std::thread::spawn(move || {
+        let mut backoff = RESTART_BACKOFF_BASE;
+
+        loop {
+            let started_at = Instant::now();
+
+            loop {
+                if shutting_down(&app) {
+                    return;
+                }
+                let exited = {
+                    let mut guard = app.state::<BackendProcess>().0.lock().unwrap();
+                    match guard.as_mut() {
+                        Some(child) => !matches!(child.try_wait(), Ok(None)),
+                        None => true,
+                    }
+                };
+                if exited {
+                    break;
+                }
+                std::thread::sleep(EXIT_POLL_INTERVAL);
+            }
+
+            if shutting_down(&app) {
+                return;
+            }
+
+            if started_at.elapsed() >= HEALTHY_RUN_THRESHOLD {
+                backoff = RESTART_BACKOFF_BASE;
+            }
+
+            tracing::warn!("Backend exited unexpectedly, restarting in {:?}", backoff);
+            let _ = app.emit("backend-restarting", backoff.as_secs());
+            std::thread::sleep(backoff);
+
+            if shutting_down(&app) {
+                return;
+            }
+
+            match spawn(&paths) {
+                Ok(child) => {
+                    *app.state::<BackendProcess>().0.lock().unwrap() = Some(child);
+                    backoff = (backoff * 2).min(RESTART_BACKOFF_MAX);
+                }
+                Err(e) => {
+                    tracing::warn!("Giving up restarting the backend: {}", e);
+                    let _ = app.emit("backend-dead", e.to_string());
+                    return;
+                }
+            }
+        }
+    });
+}
+
+// Ask the backend to shut down cleanly instead of SIGKILLing it outright,
+// so it gets a chance to flush state and release `BACKEND_PORT`.
+#[cfg(unix)]
+fn request_shutdown(pid: u32) {
+    use nix::sys::signal::{self, Signal};
+    use nix::unistd::Pid;
+    if let Err(e) = signal::kill(Pid::from_raw(pid as i32), Signal::SIGTERM) {
+        tracing::warn!("Failed to send SIGTERM to backend: {}", e);
+    }
+}
+
+#[cfg(windows)]
+fn request_shutdown(pid: u32) {
+    // Ask the backend's console process group to shut down; it's expected to
+    // handle this the same way it would handle Ctrl+Break. Requires the
+    // child to have been spawned with CREATE_NEW_PROCESS_GROUP (see
+    // `build_command`), otherwise the event has no group to target.
+    let ok = unsafe { winapi::um::wincon::GenerateConsoleCtrlEvent(winapi::um::wincon::CTRL_BREAK_EVENT, pid) };
+    if ok == 0 {
+        tracing::warn!(
+            "Failed to send CTRL_BREAK to backend (pid {}): {}",
+            pid,
+            std::io::Error::last_os_error()
+        );
+    }
+}
+
+/// Mark the shutdown as intentional (so the supervisor stops restarting),
+/// then signal the backend to shut down and block the calling thread while
+/// polling `try_wait` for up to `SHUTDOWN_WAIT_TIMEOUT` before escalating to
+/// a hard kill.
+///
+/// Must be called from `RunEvent::ExitRequested` (after `api.prevent_exit()`)
+/// rather than a window-destroyed handler: Tauri's default behavior is to
+/// exit the process as soon as the last window is destroyed, which would
+/// otherwise race this function's wait/kill against process teardown and
+/// could leave the backend orphaned if it ignores the shutdown signal.
+/// Returns once the backend has actually exited (gracefully or via the hard
+/// kill), so callers can safely release resources it still holds (e.g. the
+/// single-instance lock file) right after this returns.
+pub fn shutdown_and_wait(app: &AppHandle) {
+    app.state::<ShuttingDown>().0.store(true, Ordering::SeqCst);
+
+    let Some(mut child) = app.state::<BackendProcess>().0.lock().unwrap().take() else {
+        return;
+    };
+
+    tracing::info!("Shutting down backend process");
+    request_shutdown(child.id());
+
+    let deadline = Instant::now() + SHUTDOWN_WAIT_TIMEOUT;
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) => {
+                tracing::info!("Backend exited gracefully: {}", status);
+                break;
+            }
+            Ok(None) => {
+                if Instant::now() >= deadline {
+                    tracing::warn!("Backend did not exit in time, killing it");
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    break;
+                }
+                std::thread::sleep(SHUTDOWN_POLL_INTERVAL);
+            }
+            Err(e) => {
+                tracing::warn!("Error waiting for backend to exit: {}", e);
+                break;
+            }
+        }
+    }
+}
@@ -0,0 +1,57 @@
+// Embedded pure-Rust Python fallback.
+// =================================================================
+// When no usable system Python is found, the app used to hit a hard dead
+// end. Behind the `embedded-python` feature (off by default, since it
+// bundles a whole interpreter into the binary), this runs the backend's
+// `main.py` in-process via RustPython instead, so "install Python" becomes
+// a soft recommendation rather than a blocker.
+
+use std::path::PathBuf;
+use rustpython_vm::{Interpreter, Settings};
+
+/// Run `main.py` in-process on its own thread, with `backend_dir` added to
+/// `sys.path` so the backend's own modules resolve the same way they would
+/// under a real interpreter.
+pub fn run_embedded(backend_dir: PathBuf, backend_main: PathBuf) {
+    std::thread::spawn(move || {
+        let mut settings = Settings::default();
+        settings.path_list.push(backend_dir.to_string_lossy().into_owned());
+
+        let interpreter = Interpreter::with_init(settings, |vm| {
+            vm.add_native_modules(rustpython_stdlib::get_module_inits());
+        });
+
+        interpreter.enter(|vm| {
+            let source = match std::fs::read_to_string(&backend_main) {
+                Ok(source) => source,
+                Err(e) => {
+                    tracing::error!("Embedded interpreter: failed to read {:?}: {}", backend_main, e);
+                    return;
+                }
+            };
+
+            let code = match vm.compile(
+                &source,
+                rustpython_vm::compiler::Mode::Exec,
+                backend_main.display().to_string(),
+            ) {
+                Ok(code) => code,
+                Err(e) => {
+                    tracing::error!("Embedded interpreter: failed to compile {:?}: {}", backend_main, e);
+                    return;
+                }
+            };
+
+            let scope = vm.new_scope_with_builtins();
+            // Real entry points are almost always guarded by
+            // `if __name__ == "__main__":`; without this the backend's
+            // startup code (e.g. launching its HTTP server) never runs and
+            // the readiness probe just times out.
+            let _ = scope.globals.set_item("__name__", vm.new_pyobj("__main__"), vm);
+            if let Err(exc) = vm.run_code_obj(code, scope) {
+                vm.print_exception(exc);
+                tracing::error!("Embedded backend exited with an unhandled exception");
+            }
+        });
+    });
+}
@@ -2,61 +2,66 @@
 // =============================================
 // Startet Backend via System-Python (python main.py)
 // User muss Python + Dependencies selbst installieren.
+// Mit dem Cargo-Feature `embedded-python` wird stattdessen RustPython
+// genutzt, falls kein System-Python gefunden wird (siehe embedded_python.rs).
 
-use std::process::{Child, Command, Stdio};
+use std::net::TcpStream;
 use std::sync::Mutex;
-use std::fs::{File, OpenOptions};
-use std::io::Write;
-use tauri::Manager;
-
-#[cfg(windows)]
-use std::os::windows::process::CommandExt;
-
-// Backend process handle
-struct BackendProcess(Mutex<Option<Child>>);
-
-// Helper: Log to file for debugging
-fn log_to_file(msg: &str) {
-    if let Ok(mut file) = OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open("lutum_backend.log")
-    {
-        let _ = writeln!(file, "{}", msg);
-    }
-}
-
-/// Find system Python (python or python3)
-fn find_python() -> Option<String> {
-    // Windows: try "python" first (py launcher), then "python3"
-    for candidate in &["python", "python3"] {
-        let result = Command::new(candidate)
-            .arg("--version")
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .creation_flags(0x08000000) // CREATE_NO_WINDOW
-            .spawn();
-
-        if let Ok(mut child) = result {
-            if let Ok(status) = child.wait() {
-                if status.success() {
-                    log_to_file(&format!("Found Python: {}", candidate));
-                    return Some(candidate.to_string());
-                }
-            }
+use std::time::{Duration, Instant};
+use tauri::{Emitter, Manager, RunEvent};
+
+mod backend;
+#[cfg(feature = "embedded-python")]
+mod embedded_python;
+mod logging;
+mod python;
+mod single_instance;
+
+use backend::{BackendProcess, ShuttingDown};
+
+// Port the Python backend listens on
+pub(crate) const BACKEND_PORT: u16 = 8420;
+// How long we're willing to wait for the backend to come up before showing
+// the window anyway, so the app never hangs invisibly behind the splash.
+const BACKEND_READY_TIMEOUT: Duration = Duration::from_secs(15);
+const BACKEND_POLL_INTERVAL: Duration = Duration::from_millis(150);
+
+// Poll `127.0.0.1:BACKEND_PORT` until it accepts a connection or the timeout
+// elapses. Returns true once the backend is reachable.
+fn wait_for_backend_ready(timeout: Duration) -> bool {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if TcpStream::connect(("127.0.0.1", BACKEND_PORT)).is_ok() {
+            return true;
         }
+        if Instant::now() >= deadline {
+            return false;
+        }
+        std::thread::sleep(BACKEND_POLL_INTERVAL);
     }
-    None
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    tauri::Builder::default()
+    let app = tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
         .manage(BackendProcess(Mutex::new(None)))
+        .manage(ShuttingDown::default())
         .setup(|app| {
+            let log_dir = app.path().app_log_dir()
+                .expect("Failed to get app log dir");
+            logging::init(&log_dir);
+
+            // Bail out early if another instance already owns the lock, so we
+            // never spawn a second backend fighting over port 8420.
+            if let single_instance::LockResult::AlreadyRunning { pid } = single_instance::acquire(&app.handle().clone()) {
+                tracing::warn!("Another instance (pid {}) is already running, exiting", pid);
+                app.handle().exit(0);
+                return Ok(());
+            }
+
             // Backend source finden (im resources Ordner)
             let resource_dir = app.path().resource_dir()
                 .expect("Failed to get resource dir");
@@ -64,82 +69,89 @@ pub fn run() {
             let backend_main = resource_dir.join("lutum_backend").join("main.py");
             let backend_dir = resource_dir.join("lutum_backend");
 
-            log_to_file(&format!("Resource dir: {:?}", resource_dir));
-            log_to_file(&format!("Backend main.py: {:?}", backend_main));
-            log_to_file(&format!("Backend exists: {}", backend_main.exists()));
+            tracing::info!("Resource dir: {:?}", resource_dir);
+            tracing::info!("Backend main.py: {:?}", backend_main);
+            tracing::info!("Backend exists: {}", backend_main.exists());
+
+            // Tracks whether backend startup is already a lost cause (missing
+            // resources, no usable Python found), so we know below whether
+            // there's any point probing for readiness at all.
+            let mut backend_known_failed = false;
 
             if backend_main.exists() {
-                // Find system Python
-                match find_python() {
-                    Some(python) => {
-                        log_to_file(&format!("Using Python: {}", python));
-
-                        // Log file for backend stderr
-                        let log_file = File::create(backend_dir.join("backend_stderr.log"))
-                            .map(Stdio::from)
-                            .unwrap_or(Stdio::null());
-
-                        // Start backend hidden (CREATE_NO_WINDOW)
-                        #[cfg(windows)]
-                        let child = Command::new(&python)
-                            .arg(&backend_main)
-                            .current_dir(&backend_dir)
-                            .stdout(Stdio::null())
-                            .stderr(log_file)
-                            .creation_flags(0x08000000)
-                            .spawn();
-
-                        #[cfg(not(windows))]
-                        let child = Command::new(&python)
-                            .arg(&backend_main)
-                            .current_dir(&backend_dir)
-                            .stdout(Stdio::null())
-                            .stderr(Stdio::null())
-                            .spawn();
-
-                        match child {
-                            Ok(process) => {
-                                let state = app.state::<BackendProcess>();
-                                *state.0.lock().unwrap() = Some(process);
-                                println!("Backend started with system Python on port 8420");
-                                log_to_file("Backend started with system Python on port 8420");
-                            }
-                            Err(e) => {
-                                eprintln!("Failed to start backend: {}", e);
-                                log_to_file(&format!("Failed to start backend: {}", e));
-                            }
-                        }
+                // Find a Python interpreter (LUTUM_PYTHON override, bundled
+                // venv, or a PATH scan) that meets the minimum version.
+                match python::find_python(&app.handle().clone(), &backend_dir) {
+                    Some(discovery) => {
+                        tracing::info!("Using Python: {:?}", discovery.executable);
+                        backend::start(&app.handle().clone(), backend_main.clone(), backend_dir.clone(), discovery);
                     }
                     None => {
-                        eprintln!("Python not found! Install Python 3.11+ from python.org");
-                        log_to_file("ERROR: Python not found! Install Python 3.11+ from python.org");
+                        tracing::warn!("Python not found! Install Python 3.11+ from python.org");
+                        backend_known_failed = true;
+
+                        #[cfg(feature = "embedded-python")]
+                        {
+                            tracing::info!("Falling back to the embedded interpreter");
+                            embedded_python::run_embedded(backend_dir.clone(), backend_main.clone());
+                            backend_known_failed = false;
+                        }
                     }
                 }
             } else {
-                eprintln!("Backend main.py not found at {:?}", backend_main);
-                log_to_file(&format!("Backend main.py NOT FOUND at {:?}", backend_main));
+                tracing::warn!("Backend main.py NOT FOUND at {:?}", backend_main);
+                let _ = app.handle().emit("backend-failed", format!("Backend main.py not found at {:?}", backend_main));
+                backend_known_failed = true;
             }
 
-            // Show window after WebView had time to render the splash screen
             let main_window = app.get_webview_window("main").unwrap();
-            std::thread::spawn(move || {
-                std::thread::sleep(std::time::Duration::from_millis(1500));
+
+            if backend_known_failed {
+                // `python::find_python` (or the missing-resource check above)
+                // already emitted a specific `backend-failed` explaining why,
+                // so there's nothing to probe for and no generic message to
+                // add on top of it - show the window right away instead of
+                // burning the full readiness timeout on a backend that was
+                // never going to come up.
                 let _ = main_window.show();
-            });
+            } else {
+                // Show the window once the backend actually answers on its
+                // port, instead of guessing with a fixed sleep. The splash UI
+                // listens for `backend-ready` / `backend-failed` to update
+                // its status.
+                let app_handle = app.handle().clone();
+                std::thread::spawn(move || {
+                    if wait_for_backend_ready(BACKEND_READY_TIMEOUT) {
+                        tracing::info!("Backend is ready, showing window");
+                        let _ = app_handle.emit("backend-ready", ());
+                    } else {
+                        tracing::warn!("Timed out waiting for backend to become ready");
+                        let _ = app_handle.emit("backend-failed", "timed out waiting for backend");
+                    }
+                    // Always show the window, even on timeout, so the app
+                    // never hangs invisibly - the frontend surfaces the
+                    // failure instead.
+                    let _ = main_window.show();
+                });
+            }
 
             Ok(())
         })
-        .on_window_event(|window, event| {
-            // Bei App-Close: Backend killen
-            if let tauri::WindowEvent::Destroyed = event {
-                let state = window.state::<BackendProcess>();
-                let mut guard = state.0.lock().unwrap();
-                if let Some(mut child) = guard.take() {
-                    let _ = child.kill();
-                    println!("Backend process terminated");
-                }
-            }
-        })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application");
+
+    app.run(|app_handle, event| {
+        // Intercept the exit instead of an `on_window_event` Destroyed
+        // handler: Tauri's default behavior is to exit as soon as the last
+        // window is destroyed, which doesn't wait for anything spawned in
+        // response - `prevent_exit` plus a synchronous shutdown here is the
+        // only way to guarantee the bounded wait/kill (and the lock release
+        // that depends on it) actually runs before the process goes away.
+        if let RunEvent::ExitRequested { api, .. } = event {
+            api.prevent_exit();
+            backend::shutdown_and_wait(app_handle);
+            single_instance::release(app_handle);
+            app_handle.exit(0);
+        }
+    });
 }
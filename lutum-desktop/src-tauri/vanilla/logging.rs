@@ -0,0 +1,144 @@
+// Structured, rotating logging plus a panic hook that leaves behind a
+// crash report.
+// =================================================================
+// Replaces the old `log_to_file` helper (a single ever-growing file with
+// no levels or timestamps) with `tracing`, so "backend won't start" field
+// reports come with leveled, timestamped history instead of nothing.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tracing_appender::non_blocking::WorkerGuard;
+
+const LOG_FILE_NAME: &str = "lutum-desktop.log";
+const CRASH_LOG_FILE: &str = "lutum-crash.log";
+// Roll over to a fresh file once the active log passes this size, instead
+// of letting a chatty day (supervisor flapping, verbose backend output)
+// grow one file without bound.
+const MAX_LOG_FILE_BYTES: u64 = 5 * 1024 * 1024;
+// How many rotated files (`lutum-desktop.log.1` .. `.N`) to keep around.
+const MAX_ROTATED_FILES: u32 = 5;
+
+// Keeps the non-blocking writer's background flush thread alive for the
+// lifetime of the process; dropping it would silently stop logging.
+static LOG_GUARD: Mutex<Option<WorkerGuard>> = Mutex::new(None);
+
+fn rotated_path(base: &Path, index: u32) -> PathBuf {
+    let mut name = base.as_os_str().to_owned();
+    name.push(format!(".{}", index));
+    PathBuf::from(name)
+}
+
+// A `Write` implementation that rolls the active log file over to
+// `lutum-desktop.log.1`, `.2`, ... once it passes `MAX_LOG_FILE_BYTES`,
+// instead of tracing-appender's built-in rotation, which only rolls on a
+// time schedule (daily/hourly/...) and can't bound a single file's size.
+struct SizeCappedWriter {
+    path: PathBuf,
+    file: File,
+    size: u64,
+}
+
+impl SizeCappedWriter {
+    fn open(path: PathBuf) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let size = file.metadata()?.len();
+        Ok(Self { path, file, size })
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        for index in (1..MAX_ROTATED_FILES).rev() {
+            let from = rotated_path(&self.path, index);
+            if from.exists() {
+                let _ = fs::rename(&from, rotated_path(&self.path, index + 1));
+            }
+        }
+        let _ = fs::rename(&self.path, rotated_path(&self.path, 1));
+
+        self.file = OpenOptions::new().create(true).write(true).truncate(true).open(&self.path)?;
+        self.size = 0;
+        Ok(())
+    }
+}
+
+impl Write for SizeCappedWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.size + buf.len() as u64 > MAX_LOG_FILE_BYTES {
+            self.rotate()?;
+        }
+        let written = self.file.write(buf)?;
+        self.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+/// Install a timestamped, leveled, size-rotated file logger writing into
+/// `log_dir`. Safe to call once, early in `setup`.
+pub fn init(log_dir: &Path) {
+    if let Err(e) = std::fs::create_dir_all(log_dir) {
+        eprintln!("Failed to create log dir {:?}: {}", log_dir, e);
+        return;
+    }
+
+    let writer = match SizeCappedWriter::open(log_dir.join(LOG_FILE_NAME)) {
+        Ok(writer) => writer,
+        Err(e) => {
+            eprintln!("Failed to open log file in {:?}: {}", log_dir, e);
+            return;
+        }
+    };
+
+    let (non_blocking, guard) = tracing_appender::non_blocking(writer);
+    *LOG_GUARD.lock().unwrap() = Some(guard);
+
+    tracing_subscriber::fmt()
+        .with_writer(non_blocking)
+        .with_ansi(false)
+        .with_timer(tracing_subscriber::fmt::time::UtcTime::rfc_3339())
+        .with_max_level(tracing::Level::INFO)
+        .init();
+
+    install_panic_hook(log_dir.join(CRASH_LOG_FILE));
+}
+
+// Capture panics that would otherwise just abort the process (e.g. an
+// `.expect(...)` in `setup`) and persist them, with a backtrace, before the
+// default hook tears things down.
+fn install_panic_hook(crash_log_path: PathBuf) {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |panic_info| {
+        let backtrace = std::backtrace::Backtrace::force_capture();
+        let report = format!(
+            "[{}] {}\n\nbacktrace:\n{}\n",
+            humantime_like_timestamp(),
+            panic_info,
+            backtrace
+        );
+
+        tracing::error!("panic: {}", panic_info);
+
+        if let Ok(mut file) = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&crash_log_path)
+        {
+            let _ = file.write_all(report.as_bytes());
+        }
+
+        default_hook(panic_info);
+    }));
+}
+
+// Avoids pulling in a dedicated time-formatting dependency just for the
+// crash log; `tracing`'s own timer handles the rotating log already.
+fn humantime_like_timestamp() -> String {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default();
+    format!("unix:{}", now.as_secs())
+}
@@ -0,0 +1,282 @@
+// Python interpreter discovery.
+// =================================================================
+// Mirrors the discovery logic used by rustc's bootstrap launcher: an
+// explicit override env var wins outright, then a virtualenv bundled
+// alongside the backend, then a PATH scan preferring a plain `python`
+// over `python3` over `python2`.
+
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use tauri::{AppHandle, Emitter};
+
+#[cfg(windows)]
+use std::os::windows::process::CommandExt;
+
+// Backend requires Python 3.11+ (match/case-free syntax it relies on is
+// fine earlier, but it also uses 3.11-only stdlib additions).
+const MINIMUM_PYTHON_VERSION: (u32, u32) = (3, 11);
+
+// Lets users pin an exact interpreter, bypassing discovery entirely.
+const PYTHON_OVERRIDE_ENV: &str = "LUTUM_PYTHON";
+
+/// A discovered interpreter, plus the virtualenv it belongs to (if any) so
+/// the caller can set `VIRTUAL_ENV` in the backend's environment.
+pub struct PythonDiscovery {
+    pub executable: PathBuf,
+    pub virtual_env: Option<PathBuf>,
+}
+
+// Parse `python --version` output such as "Python 3.11.4" into (major, minor).
+// Older Python 2 builds print to stderr instead of stdout, so callers should
+// pass the combined output of both streams.
+fn parse_python_version(version_output: &str) -> Option<(u32, u32)> {
+    let version_str = version_output.trim().strip_prefix("Python ")?;
+    let mut parts = version_str.split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.parse().ok()?;
+    Some((major, minor))
+}
+
+fn check_version(candidate: &Path) -> Option<(u32, u32)> {
+    let mut command = Command::new(candidate);
+    command.arg("--version").stdout(Stdio::piped()).stderr(Stdio::piped());
+    #[cfg(windows)]
+    command.creation_flags(0x08000000); // CREATE_NO_WINDOW
+
+    let output = command.output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let combined = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    parse_python_version(&combined)
+}
+
+// Locate a `.venv`/`venv` folder bundled under the backend resource dir.
+fn bundled_venv(backend_dir: &Path) -> Option<PathBuf> {
+    for name in [".venv", "venv"] {
+        let venv_dir = backend_dir.join(name);
+        if venv_dir.is_dir() {
+            return Some(venv_dir);
+        }
+    }
+    None
+}
+
+fn venv_python(venv_dir: &Path) -> PathBuf {
+    #[cfg(windows)]
+    {
+        venv_dir.join("Scripts").join("python.exe")
+    }
+    #[cfg(not(windows))]
+    {
+        venv_dir.join("bin").join("python3")
+    }
+}
+
+// Scan every directory on PATH for `python`, `python3`, then `python2`
+// (in that preference order), using the platform's executable extension.
+// Standard PATH precedence picks the first matching directory per name.
+fn candidates_on_path() -> Vec<PathBuf> {
+    let Some(path_var) = env::var_os("PATH") else {
+        return Vec::new();
+    };
+    let dirs: Vec<PathBuf> = env::split_paths(&path_var).collect();
+    candidates_in(&dirs)
+}
+
+// Name-preference ordering, factored out of `candidates_on_path` so it can
+// be exercised against a controlled set of directories instead of the real
+// PATH.
+fn candidates_in(dirs: &[PathBuf]) -> Vec<PathBuf> {
+    ["python", "python3", "python2"]
+        .into_iter()
+        .filter_map(|name| {
+            let exe_name = format!("{}{}", name, env::consts::EXE_SUFFIX);
+            dirs.iter().map(|dir| dir.join(&exe_name)).find(|path| path.is_file())
+        })
+        .collect()
+}
+
+/// Find a Python interpreter suitable for running the backend.
+///
+/// Honors `LUTUM_PYTHON` first, then a bundled virtualenv under
+/// `backend_dir`, then falls back to scanning `PATH`. PATH candidates below
+/// `MINIMUM_PYTHON_VERSION` are rejected; if every candidate is too old, a
+/// `backend-failed` event names the newest one found so the user sees
+/// "found Python 3.9, need 3.11+" instead of a silent failure.
+pub fn find_python(app: &AppHandle, backend_dir: &Path) -> Option<PythonDiscovery> {
+    if let Some(path) = env::var_os(PYTHON_OVERRIDE_ENV) {
+        let executable = PathBuf::from(path);
+        // An override still has to clear the minimum version - otherwise
+        // pointing it at a stale interpreter launches the backend anyway,
+        // which then just crashes on 3.11-only stdlib usage instead of surfacing
+        // a clear "need 3.11+" failure.
+        match check_version(&executable) {
+            Some(version) if version >= MINIMUM_PYTHON_VERSION => {
+                tracing::info!("{} set, using {:?} (Python {}.{})", PYTHON_OVERRIDE_ENV, executable, version.0, version.1);
+                return Some(PythonDiscovery { executable, virtual_env: None });
+            }
+            Some(version) => {
+                let message = format!(
+                    "{} points at Python {}.{} ({:?}), but Lutum Veritas requires Python {}.{}+",
+                    PYTHON_OVERRIDE_ENV, version.0, version.1, executable, MINIMUM_PYTHON_VERSION.0, MINIMUM_PYTHON_VERSION.1
+                );
+                tracing::warn!("{}", message);
+                let _ = app.emit("backend-failed", message);
+                return None;
+            }
+            None => {
+                let message = format!(
+                    "{} is set to {:?}, but its version could not be determined",
+                    PYTHON_OVERRIDE_ENV, executable
+                );
+                tracing::warn!("{}", message);
+                let _ = app.emit("backend-failed", message);
+                return None;
+            }
+        }
+    }
+
+    let mut rejected: Option<(PathBuf, (u32, u32))> = None;
+
+    if let Some(venv_dir) = bundled_venv(backend_dir) {
+        let executable = venv_python(&venv_dir);
+        if executable.is_file() {
+            match check_version(&executable) {
+                Some(version) if version >= MINIMUM_PYTHON_VERSION => {
+                    tracing::info!("Using bundled virtualenv at {:?} (Python {}.{})", venv_dir, version.0, version.1);
+                    return Some(PythonDiscovery { executable, virtual_env: Some(venv_dir) });
+                }
+                Some(version) => {
+                    tracing::warn!(
+                        "Bundled virtualenv at {:?} has Python {}.{}, need {}.{}+; ignoring it",
+                        venv_dir, version.0, version.1, MINIMUM_PYTHON_VERSION.0, MINIMUM_PYTHON_VERSION.1
+                    );
+                    rejected = Some((executable, version));
+                }
+                None => {
+                    tracing::warn!("Could not determine version of bundled virtualenv interpreter {:?}; ignoring it", executable);
+                }
+            }
+        }
+    }
+    for candidate in candidates_on_path() {
+        match check_version(&candidate) {
+            Some(version) if version >= MINIMUM_PYTHON_VERSION => {
+                tracing::info!("Found Python {:?}: {}.{}", candidate, version.0, version.1);
+                return Some(PythonDiscovery { executable: candidate, virtual_env: None });
+            }
+            Some(version) => {
+                tracing::warn!(
+                    "Rejected {:?} {}.{}: need {}.{}+",
+                    candidate, version.0, version.1, MINIMUM_PYTHON_VERSION.0, MINIMUM_PYTHON_VERSION.1
+                );
+                // Keep the newest rejected candidate, not just the last one
+                // scanned, so the reported version is the most informative.
+                let is_newer = match &rejected {
+                    Some((_, rejected_version)) => version > *rejected_version,
+                    None => true,
+                };
+                if is_newer {
+                    rejected = Some((candidate, version));
+                }
+            }
+            None => {
+                tracing::warn!("Could not determine version for {:?}", candidate);
+            }
+        }
+    }
+
+    let message = match rejected {
+        Some((candidate, version)) => format!(
+            "Found Python {}.{} ({:?}), but Lutum Veritas requires Python {}.{}+",
+            version.0, version.1, candidate, MINIMUM_PYTHON_VERSION.0, MINIMUM_PYTHON_VERSION.1
+        ),
+        // Not even a too-old interpreter was found anywhere.
+        None => format!(
+            "No Python {}.{}+ interpreter found. Install Python from python.org, or set {} to point at one.",
+            MINIMUM_PYTHON_VERSION.0, MINIMUM_PYTHON_VERSION.1, PYTHON_OVERRIDE_ENV
+        ),
+    };
+    tracing::warn!("{}", message);
+    let _ = app.emit("backend-failed", message);
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_three_component_version() {
+        assert_eq!(parse_python_version("Python 3.11.4"), Some((3, 11)));
+    }
+
+    #[test]
+    fn parses_two_component_version() {
+        assert_eq!(parse_python_version("Python 3.9"), Some((3, 9)));
+    }
+
+    #[test]
+    fn parses_with_surrounding_whitespace() {
+        assert_eq!(parse_python_version("Python 3.11.4\n"), Some((3, 11)));
+    }
+
+    #[test]
+    fn rejects_garbage() {
+        assert_eq!(parse_python_version(""), None);
+        assert_eq!(parse_python_version("not a version string"), None);
+        assert_eq!(parse_python_version("Python"), None);
+        assert_eq!(parse_python_version("Python x.y.z"), None);
+    }
+
+    #[test]
+    fn parses_python_2_style_output() {
+        // Python 2 prints "Python 2.7.18" to stderr rather than stdout, but
+        // `check_version` combines both streams before parsing, so the
+        // format seen here is the same either way.
+        assert_eq!(parse_python_version("Python 2.7.18"), Some((2, 7)));
+    }
+
+    #[test]
+    fn candidates_in_prefers_python_over_python3_over_python2() {
+        let dir = std::env::temp_dir().join(format!("lutum_python_candidates_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for name in ["python", "python3", "python2"] {
+            std::fs::write(dir.join(format!("{}{}", name, env::consts::EXE_SUFFIX)), b"").unwrap();
+        }
+
+        let candidates = candidates_in(&[dir.clone()]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            candidates,
+            vec![
+                dir.join(format!("python{}", env::consts::EXE_SUFFIX)),
+                dir.join(format!("python3{}", env::consts::EXE_SUFFIX)),
+                dir.join(format!("python2{}", env::consts::EXE_SUFFIX)),
+            ]
+        );
+    }
+
+    #[test]
+    fn candidates_in_skips_missing_names() {
+        let dir = std::env::temp_dir().join(format!("lutum_python_candidates_partial_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join(format!("python3{}", env::consts::EXE_SUFFIX)), b"").unwrap();
+
+        let candidates = candidates_in(&[dir.clone()]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(candidates, vec![dir.join(format!("python3{}", env::consts::EXE_SUFFIX))]);
+    }
+}
@@ -0,0 +1,112 @@
+// Single-instance guard via a PID lock file.
+// =================================================================
+// Launching the app twice used to spawn two backends fighting over port
+// 8420. A lock file in the app data dir records which PID owns the
+// instance; a second launch detects the live primary and steps aside
+// instead of spawning its own backend.
+
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+
+const LOCK_FILE_NAME: &str = "lutum.lock";
+
+#[cfg(unix)]
+fn is_process_alive(pid: u32) -> bool {
+    use nix::sys::signal::kill;
+    use nix::unistd::Pid;
+    // Signal 0 performs no-op existence/permission checks without
+    // actually signaling the process.
+    kill(Pid::from_raw(pid as i32), None).is_ok()
+}
+
+#[cfg(windows)]
+fn is_process_alive(pid: u32) -> bool {
+    use winapi::um::handleapi::CloseHandle;
+    use winapi::um::processthreadsapi::OpenProcess;
+    use winapi::um::winnt::PROCESS_QUERY_LIMITED_INFORMATION;
+    unsafe {
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+        if handle.is_null() {
+            false
+        } else {
+            CloseHandle(handle);
+            true
+        }
+    }
+}
+
+fn lock_path(app: &AppHandle) -> PathBuf {
+    let dir = app.path().app_data_dir().expect("Failed to get app data dir");
+    dir.join(LOCK_FILE_NAME)
+}
+
+pub enum LockResult {
+    Acquired,
+    AlreadyRunning { pid: u32 },
+}
+
+/// Try to become the single running instance. If a live process already
+/// holds the lock, returns `AlreadyRunning` without touching the file; a
+/// stale lock (owning process no longer alive) is reclaimed automatically.
+///
+/// Acquisition is atomic: the lock file is created with `create_new`, which
+/// fails outright if it already exists, instead of a plain read-then-write.
+/// A read-then-write has a window where two instances launched back-to-back
+/// both see no live owner and both proceed to write the file - the second
+/// write just clobbers the first, and both end up spawning their own
+/// backend on the same port.
+pub fn acquire(app: &AppHandle) -> LockResult {
+    use std::io::{ErrorKind, Write};
+
+    let path = lock_path(app);
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+
+    loop {
+        match std::fs::OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(mut file) => {
+                if let Err(e) = write!(file, "{}", std::process::id()) {
+                    tracing::warn!("Failed to write lock file {:?}: {}", path, e);
+                }
+                return LockResult::Acquired;
+            }
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                let owner = std::fs::read_to_string(&path)
+                    .ok()
+                    .and_then(|contents| contents.trim().parse::<u32>().ok());
+                if let Some(pid) = owner {
+                    if pid != std::process::id() && is_process_alive(pid) {
+                        return LockResult::AlreadyRunning { pid };
+                    }
+                    tracing::info!("Lock file pid {} is stale, reclaiming", pid);
+                } else {
+                    tracing::warn!("Lock file {:?} exists but couldn't be read; reclaiming", path);
+                }
+                if let Err(e) = std::fs::remove_file(&path) {
+                    tracing::warn!("Failed to remove stale lock file {:?}: {}", path, e);
+                    return LockResult::Acquired;
+                }
+                // Race `create_new` again - another instance may have
+                // recreated the file between our remove and the next loop.
+            }
+            Err(e) => {
+                tracing::warn!("Failed to create lock file {:?}: {}", path, e);
+                return LockResult::Acquired;
+            }
+        }
+    }
+}
+
+/// Remove the lock file on clean shutdown so the next launch doesn't have
+/// to wait for a stale-PID check. A lock held by another process (e.g. the
+/// live primary, when this instance stepped aside) is left in place.
+pub fn release(app: &AppHandle) {
+    let path = lock_path(app);
+    let owner = std::fs::read_to_string(&path)
+        .ok()
+        .and_then(|contents| contents.trim().parse::<u32>().ok());
+    if owner == Some(std::process::id()) {
+        let _ = std::fs::remove_file(path);
+    }
+}